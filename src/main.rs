@@ -1,25 +1,47 @@
 use anyhow::{Context, Result};
-use clap::Parser;
-use crossbeam_channel::unbounded;
+use clap::{Args as ClapArgs, Parser, Subcommand};
+use crossbeam_channel::{unbounded, Sender};
 use dashmap::DashMap;
 use indicatif::{ProgressBar, ProgressStyle};
 use jwalk::WalkDir;
+use rand::Rng;
 use rayon::iter::{ParallelBridge, ParallelIterator};
 use redb::{Database, ReadableDatabase, TableDefinition};
 use reqwest::blocking::Client;
 use rustls::{ClientConfig, RootCertStore};
 use std::fs;
+use std::io::Read;
 use std::path::{Path, PathBuf};
-use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
 use std::thread;
-use std::time::{Duration, UNIX_EPOCH};
+use std::time::{Duration, Instant, UNIX_EPOCH};
+
+// 分块上传的块大小：限速时以此粒度申请令牌，让限速更平滑
+const CHUNK_SIZE: usize = 256 * 1024;
 
 // 定义本地数据库表：Key 是目标 URL，Value 是文件最后修改时间 (u64)
 const TABLE: TableDefinition<&str, u64> = TableDefinition::new("uploads_v1");
 
 #[derive(Parser, Debug, Clone)]
 #[command(author, version, about = "高性能 Maven 仓库迁移工具 (Pure Rust 版)")]
-struct Args {
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+enum Command {
+    /// 上传本地构件到 Nexus 仓库
+    Upload(UploadArgs),
+    /// 从远程仓库镜像下载到本地 Maven 布局
+    Download(DownloadArgs),
+    /// 测量链路与目标服务器的吞吐/延迟 (不改动真实构件)
+    Benchmark(BenchmarkArgs),
+}
+
+#[derive(ClapArgs, Debug, Clone)]
+struct UploadArgs {
     /// Release 仓库 URL
     #[arg(short = 'U', long, env = "NEXUS_URL")]
     url: String,
@@ -55,6 +77,223 @@ struct Args {
     /// 状态数据库路径 (redb 格式)
     #[arg(long, default_value = "uploader_state.db")]
     db_path: String,
+
+    /// 全局带宽上限 (bytes/sec, 支持 10MiB 等后缀, 需 > 0), 跨所有上传线程共享;
+    /// 仅对 http 后端生效, local 与 s3 后端不限速
+    #[arg(long, value_parser = parse_rate_bytes)]
+    rate: Option<u64>,
+
+    /// 令牌桶突发容量 (bytes, 支持后缀), 默认等于 rate
+    #[arg(long, value_parser = parse_human_bytes)]
+    burst: Option<u64>,
+
+    /// 自动生成并上传的校验和集合 (逗号分隔: md5,sha1,sha256,sha512)
+    #[arg(long, value_delimiter = ',', default_value = "md5,sha1")]
+    checksums: Vec<String>,
+
+    /// 即使本地已存在校验和文件也重新生成并上传
+    #[arg(long)]
+    regen_checksums: bool,
+
+    /// 存储后端: http (Nexus PUT) | local (本地 Maven 布局) | s3
+    #[arg(long, value_enum, default_value_t = BackendKind::Http)]
+    backend: BackendKind,
+
+    /// local 后端输出目录 (构建离线镜像/气隙包)
+    #[arg(long)]
+    local_dir: Option<String>,
+
+    /// s3 后端 bucket 名称
+    #[arg(long)]
+    s3_bucket: Option<String>,
+
+    /// s3 region (兼容 MinIO 时填自定义 endpoint 对应的 region)
+    #[arg(long, default_value = "us-east-1")]
+    s3_region: String,
+
+    /// s3 自定义 endpoint (MinIO 等 S3 兼容服务)
+    #[arg(long)]
+    s3_endpoint: Option<String>,
+
+    /// 常驻守护模式: 扫描完成后继续监听目录, 新构件落地即上传
+    #[arg(long)]
+    watch: bool,
+
+    /// 控制面 HTTP 监听地址 (如 127.0.0.1:9000), 暴露 JSON 状态与 /healthz
+    #[arg(long)]
+    listen: Option<String>,
+
+    /// 传输失败 (连接错误/5xx/429) 的最大重试次数
+    #[arg(long, default_value_t = 4)]
+    max_retries: u32,
+
+    /// 指数退避基准 (毫秒), 实际等待 base*2^attempt (上限 60 秒) 再叠加随机抖动
+    #[arg(long, default_value_t = 500)]
+    retry_base_ms: u64,
+
+    /// 预演模式: 跑完整扫描/排除/跳过判定, 只打印将上传的数量与字节数, 不发起任何 PUT
+    #[arg(long)]
+    dry_run: bool,
+}
+
+#[derive(ClapArgs, Debug, Clone)]
+struct BenchmarkArgs {
+    /// 目标仓库 URL
+    #[arg(short = 'U', long, env = "NEXUS_URL")]
+    url: String,
+
+    /// 用户名 (可选)
+    #[arg(short = 'u', long, env = "NEXUS_USERNAME")]
+    username: Option<String>,
+
+    /// 密码 (可选)
+    #[arg(short = 'p', long, env = "NEXUS_PASSWORD")]
+    password: Option<String>,
+
+    /// 并行 worker 数 (测量有效并行度)
+    #[arg(short = 'P', long, default_value_t = 4)]
+    parallelism: usize,
+
+    /// 合成负载写入的临时路径 (相对 --url)
+    #[arg(long, default_value = ".maven-uploader-benchmark")]
+    temp_path: String,
+}
+
+#[derive(clap::ValueEnum, Debug, Clone)]
+enum BackendKind {
+    Http,
+    Local,
+    S3,
+}
+
+#[derive(ClapArgs, Debug, Clone)]
+struct DownloadArgs {
+    /// 源仓库 URL (镜像起点)
+    #[arg(short = 'U', long, env = "NEXUS_URL")]
+    url: String,
+
+    /// 用户名 (公共仓库可留空)
+    #[arg(short = 'u', long, env = "NEXUS_USERNAME")]
+    username: Option<String>,
+
+    /// 密码 (公共仓库可留空)
+    #[arg(short = 'p', long, env = "NEXUS_PASSWORD")]
+    password: Option<String>,
+
+    /// 输出根目录 (将在其下重建 groupId/artifactId/version 树)
+    #[arg(short = 'd', long, env = "NEXUS_DIR", default_value = ".")]
+    dir: String,
+
+    /// 要镜像的组前缀 (逗号分隔), 如 com.example,org.foo
+    #[arg(short = 'g', long, value_delimiter = ',')]
+    groups: Vec<String>,
+
+    /// 是否强制重新下载
+    #[arg(short = 'f', long, env = "NEXUS_FORCE")]
+    force: bool,
+
+    /// 状态数据库路径 (redb 格式)
+    #[arg(long, default_value = "uploader_state.db")]
+    db_path: String,
+}
+
+/// 解析带单位后缀的字节数，如 `512`, `10KiB`, `20MiB`, `1GiB`
+fn parse_human_bytes(s: &str) -> Result<u64, String> {
+    let s = s.trim();
+    let (num, mult) = if let Some(n) = s.strip_suffix("GiB") {
+        (n, 1024 * 1024 * 1024)
+    } else if let Some(n) = s.strip_suffix("MiB") {
+        (n, 1024 * 1024)
+    } else if let Some(n) = s.strip_suffix("KiB") {
+        (n, 1024)
+    } else if let Some(n) = s.strip_suffix('B') {
+        (n, 1)
+    } else {
+        (s, 1)
+    };
+    let val: f64 = num.trim().parse().map_err(|_| format!("无法解析字节数: {}", s))?;
+    Ok((val * mult as f64) as u64)
+}
+
+/// 解析 `--rate`: 同 `parse_human_bytes`, 但拒绝 0 (避免除零导致的无限等待 panic)
+fn parse_rate_bytes(s: &str) -> Result<u64, String> {
+    let val = parse_human_bytes(s)?;
+    if val == 0 {
+        return Err("--rate 必须大于 0 (省略该参数即为不限速)".to_string());
+    }
+    Ok(val)
+}
+
+// 全局令牌桶限速器：由 Arc 包裹后随 Client 一起传递给所有上传线程
+struct RateLimiter {
+    capacity: f64,
+    rate: f64,
+    inner: Mutex<BucketState>,
+}
+
+struct BucketState {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl RateLimiter {
+    fn new(rate: u64, burst: u64) -> Self {
+        RateLimiter {
+            capacity: burst as f64,
+            rate: rate as f64,
+            inner: Mutex::new(BucketState {
+                tokens: burst as f64,
+                last_refill: Instant::now(),
+            }),
+        }
+    }
+
+    /// 申请 `len` 字节的配额；令牌不足时阻塞当前线程直到补足
+    fn consume(&self, len: usize) {
+        let len = len as f64;
+        // 单次请求至多需要一桶 (chunk 可能大于 burst), 以免永远攒不够而死锁
+        let target = len.min(self.capacity);
+        loop {
+            let mut state = self.inner.lock().unwrap();
+            let now = Instant::now();
+            let elapsed = now.duration_since(state.last_refill).as_secs_f64();
+            state.last_refill = now;
+            state.tokens = self.capacity.min(state.tokens + elapsed * self.rate);
+
+            if state.tokens >= target {
+                // 仅扣减实际用量, 不清零——避免睡眠期间其它线程的补充被抹掉
+                state.tokens -= len;
+                return;
+            }
+
+            let wait = (target - state.tokens) / self.rate;
+            drop(state);
+            thread::sleep(Duration::from_secs_f64(wait));
+            // 回到循环顶部, 重新依 elapsed 补充令牌后重试扣减
+        }
+    }
+}
+
+// 按块读取内存数据的 Reader：每块发送前向令牌桶申请配额，实现平滑限速
+struct ThrottledReader {
+    data: Vec<u8>,
+    pos: usize,
+    limiter: Option<Arc<RateLimiter>>,
+}
+
+impl Read for ThrottledReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if self.pos >= self.data.len() {
+            return Ok(0);
+        }
+        let n = CHUNK_SIZE.min(buf.len()).min(self.data.len() - self.pos);
+        if let Some(limiter) = &self.limiter {
+            limiter.consume(n);
+        }
+        buf[..n].copy_from_slice(&self.data[self.pos..self.pos + n]);
+        self.pos += n;
+        Ok(n)
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -99,7 +338,297 @@ fn create_pure_rust_client() -> Result<Client> {
 }
 
 fn main() -> Result<()> {
-    let args = Arc::new(Args::parse());
+    let cli = Cli::parse();
+    match cli.command {
+        Command::Upload(args) => run_upload(Arc::new(args)),
+        Command::Download(args) => run_download(Arc::new(args)),
+        Command::Benchmark(args) => run_benchmark(args),
+    }
+}
+
+/// 实时计数器：由上传消费端更新, 由控制面 HTTP 读取
+#[derive(Default)]
+struct Stats {
+    queued: AtomicU64,
+    uploaded: AtomicU64,
+    skipped: AtomicU64,
+    failed: AtomicU64,
+    bytes: AtomicU64,
+    current: Mutex<String>,
+}
+
+impl Stats {
+    /// 导出为 JSON 字符串供 /stats 端点返回
+    fn snapshot_json(&self) -> String {
+        let current = self.current.lock().unwrap().clone();
+        format!(
+            "{{\"queued\":{},\"uploaded\":{},\"skipped\":{},\"failed\":{},\"bytes\":{},\"current\":{:?}}}",
+            self.queued.load(Ordering::Relaxed),
+            self.uploaded.load(Ordering::Relaxed),
+            self.skipped.load(Ordering::Relaxed),
+            self.failed.load(Ordering::Relaxed),
+            self.bytes.load(Ordering::Relaxed),
+            current,
+        )
+    }
+}
+
+/// 守护模式的协调对象：持有生产端 sender、共享计数器与关停标志
+struct Controller {
+    tx: Sender<MavenArtifact>,
+    stats: Arc<Stats>,
+    shutdown: Arc<AtomicBool>,
+}
+
+/// 启动嵌入式控制面 HTTP 服务: /healthz、/shutdown 及 JSON 状态
+fn serve_control(listen: String, stats: Arc<Stats>, shutdown: Arc<AtomicBool>) -> Result<()> {
+    let server = tiny_http::Server::http(&listen).map_err(|e| anyhow::anyhow!("无法绑定 {}: {}", listen, e))?;
+    for request in server.incoming_requests() {
+        let url = request.url().to_string();
+        let response = if url.starts_with("/healthz") {
+            tiny_http::Response::from_string("ok")
+        } else if url.starts_with("/shutdown") {
+            shutdown.store(true, Ordering::SeqCst);
+            tiny_http::Response::from_string("shutting down")
+        } else {
+            let header = tiny_http::Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..]).unwrap();
+            tiny_http::Response::from_string(stats.snapshot_json()).with_header(header)
+        };
+        let _ = request.respond(response);
+        if shutdown.load(Ordering::SeqCst) {
+            break;
+        }
+    }
+    Ok(())
+}
+
+/// 监听目录变化, 每当 `.pom`/`pom.xml` 出现或改动就重新入队.
+/// 按值接收 `Controller`: 收到关停信号后函数返回, 其持有的 `tx` 随之析构,
+/// 消费端的 `par_bridge` 因通道关闭而 drain-and-exit, 守护进程得以停止.
+fn watch_directory(controller: Controller, args: &UploadArgs, root_path: &Path) -> Result<()> {
+    use notify::{RecursiveMode, Watcher};
+    use std::sync::mpsc::RecvTimeoutError;
+    let (ntx, nrx) = std::sync::mpsc::channel();
+    let mut watcher = notify::recommended_watcher(move |res| {
+        let _ = ntx.send(res);
+    })?;
+    watcher.watch(Path::new(&args.dir), RecursiveMode::Recursive)?;
+
+    loop {
+        // 定期醒来轮询关停标志, 不再阻塞到下一次文件事件
+        if controller.shutdown.load(Ordering::SeqCst) {
+            break;
+        }
+        let event = match nrx.recv_timeout(Duration::from_millis(500)) {
+            Ok(Ok(e)) => e,
+            Ok(Err(_)) => continue,
+            Err(RecvTimeoutError::Timeout) => continue,
+            Err(RecvTimeoutError::Disconnected) => break,
+        };
+        for path in event.paths {
+            let name = path.file_name().unwrap_or_default().to_string_lossy();
+            if (name.ends_with(".pom") || name == "pom.xml") && path.is_file() {
+                if let Ok(art) = extract_full_artifact(&path, root_path) {
+                    controller.stats.queued.fetch_add(1, Ordering::Relaxed);
+                    let _ = controller.tx.send(art);
+                }
+            }
+        }
+    }
+    // controller (含 tx) 在此析构, 关闭通道以通知消费端退出
+    Ok(())
+}
+
+/// 目标坐标：`relative` 是仓库内相对路径 (本地/S3 key), `url` 是完整 HTTP 目标
+struct Coord {
+    relative: String,
+    url: String,
+}
+
+impl Coord {
+    /// 追加后缀派生出伴随文件坐标 (如 `.sha1`)
+    fn with_suffix(&self, suffix: &str) -> Coord {
+        Coord {
+            relative: format!("{}{}", self.relative, suffix),
+            url: format!("{}{}", self.url, suffix),
+        }
+    }
+}
+
+/// 存储后端抽象：让同一套扫描/去重/redb 状态机可以喂给任意 sink
+trait Backend {
+    /// 目标是否已存在；存在时返回其大小 (字节), 不存在返回 None
+    fn exists(&self, coord: &Coord) -> Result<Option<u64>>;
+    /// 写入构件字节
+    fn put(&self, coord: &Coord, bytes: Vec<u8>) -> Result<()>;
+}
+
+/// 默认后端：对 Nexus 风格 HTTP API 做 HEAD/PUT, 带指数退避重试
+struct HttpBackend {
+    client: Arc<Client>,
+    username: String,
+    password: String,
+    limiter: Option<Arc<RateLimiter>>,
+    max_retries: u32,
+    retry_base: Duration,
+}
+
+/// 是否属于值得重试的瞬态响应 (5xx 或 429 限流)
+fn is_retriable(status: reqwest::StatusCode) -> bool {
+    status.is_server_error() || status.as_u16() == 429
+}
+
+/// 计算本次重试前的等待时长：优先服务端 `Retry-After`, 否则退避 + 抖动
+fn retry_delay(headers: Option<&reqwest::header::HeaderMap>, base: Duration, attempt: u32) -> Duration {
+    if let Some(secs) = headers
+        .and_then(|h| h.get(reqwest::header::RETRY_AFTER))
+        .and_then(|v| v.to_str().ok())
+        .and_then(|s| s.trim().parse::<u64>().ok())
+    {
+        return Duration::from_secs(secs);
+    }
+    // 退避上限, 防止大 --max-retries 时指数移位/乘法溢出 panic
+    const MAX_BACKOFF: Duration = Duration::from_secs(60);
+    let factor = 1u64.checked_shl(attempt).unwrap_or(u64::MAX);
+    let backoff = base
+        .as_millis()
+        .saturating_mul(factor as u128)
+        .min(MAX_BACKOFF.as_millis()) as u64;
+    let jitter = rand::thread_rng().gen_range(0..=base.as_millis() as u64);
+    Duration::from_millis(backoff) + Duration::from_millis(jitter)
+}
+
+impl Backend for HttpBackend {
+    fn exists(&self, coord: &Coord) -> Result<Option<u64>> {
+        for attempt in 0..=self.max_retries {
+            match self.client.head(&coord.url).basic_auth(&self.username, Some(&self.password)).send() {
+                Ok(r) if r.status().is_success() => {
+                    let len = r
+                        .headers()
+                        .get(reqwest::header::CONTENT_LENGTH)
+                        .and_then(|v| v.to_str().ok())
+                        .and_then(|s| s.parse().ok())
+                        .unwrap_or(0);
+                    return Ok(Some(len));
+                }
+                Ok(r) if is_retriable(r.status()) && attempt < self.max_retries => {
+                    thread::sleep(retry_delay(Some(r.headers()), self.retry_base, attempt));
+                }
+                // 非瞬态响应 (如 404) 即视为不存在
+                Ok(_) => return Ok(None),
+                // 连接错误重试; 重试耗尽后保守地按"不存在"处理, 交给 PUT 去尝试
+                Err(_) if attempt < self.max_retries => {
+                    thread::sleep(retry_delay(None, self.retry_base, attempt));
+                }
+                Err(_) => return Ok(None),
+            }
+        }
+        Ok(None)
+    }
+
+    fn put(&self, coord: &Coord, bytes: Vec<u8>) -> Result<()> {
+        let len = bytes.len() as u64;
+        let mut last_err = String::new();
+        for attempt in 0..=self.max_retries {
+            // 每次重试重建按块限速的流式 Body
+            let reader = ThrottledReader { data: bytes.clone(), pos: 0, limiter: self.limiter.clone() };
+            let body = reqwest::blocking::Body::sized(reader, len);
+            match self.client.put(&coord.url).basic_auth(&self.username, Some(&self.password)).body(body).send() {
+                Ok(resp) if resp.status().is_success() => return Ok(()),
+                Ok(resp) if is_retriable(resp.status()) && attempt < self.max_retries => {
+                    last_err = format!("HTTP {}", resp.status());
+                    thread::sleep(retry_delay(Some(resp.headers()), self.retry_base, attempt));
+                }
+                Ok(resp) => {
+                    let status = resp.status();
+                    let msg = resp.text().unwrap_or_default();
+                    return Err(anyhow::anyhow!("PUT 失败 ({}): {}", status, msg));
+                }
+                Err(e) if attempt < self.max_retries => {
+                    last_err = e.to_string();
+                    thread::sleep(retry_delay(None, self.retry_base, attempt));
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+        Err(anyhow::anyhow!("重试 {} 次后仍失败: {}", self.max_retries, last_err))
+    }
+}
+
+/// 本地文件系统后端：把构件复制成合法的 Maven 仓库布局
+struct LocalFsBackend {
+    root: PathBuf,
+}
+
+impl Backend for LocalFsBackend {
+    fn exists(&self, coord: &Coord) -> Result<Option<u64>> {
+        let path = self.root.join(&coord.relative);
+        Ok(fs::metadata(&path).ok().map(|m| m.len()))
+    }
+
+    fn put(&self, coord: &Coord, bytes: Vec<u8>) -> Result<()> {
+        let path = self.root.join(&coord.relative);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&path, bytes)?;
+        Ok(())
+    }
+}
+
+/// S3 后端：object key 即 group/artifact/version 路径
+struct S3Backend {
+    bucket: s3::Bucket,
+}
+
+impl Backend for S3Backend {
+    fn exists(&self, coord: &Coord) -> Result<Option<u64>> {
+        match self.bucket.head_object_blocking(&coord.relative) {
+            Ok((head, _)) => Ok(Some(head.content_length.unwrap_or(0) as u64)),
+            Err(_) => Ok(None),
+        }
+    }
+
+    fn put(&self, coord: &Coord, bytes: Vec<u8>) -> Result<()> {
+        self.bucket.put_object_blocking(&coord.relative, &bytes).context("S3 put 失败")?;
+        Ok(())
+    }
+}
+
+/// 按 `--backend` 构建具体后端实例
+fn build_backend(args: &UploadArgs, limiter: Option<Arc<RateLimiter>>) -> Result<Box<dyn Backend + Send + Sync>> {
+    match args.backend {
+        BackendKind::Http => {
+            let client = Arc::new(create_pure_rust_client()?);
+            Ok(Box::new(HttpBackend {
+                client,
+                username: args.username.clone(),
+                password: args.password.clone(),
+                limiter,
+                max_retries: args.max_retries,
+                retry_base: Duration::from_millis(args.retry_base_ms),
+            }))
+        }
+        BackendKind::Local => {
+            let dir = args.local_dir.as_ref().context("local 后端需要 --local-dir")?;
+            Ok(Box::new(LocalFsBackend { root: PathBuf::from(dir) }))
+        }
+        BackendKind::S3 => {
+            let name = args.s3_bucket.as_ref().context("s3 后端需要 --s3-bucket")?;
+            let region = match &args.s3_endpoint {
+                Some(endpoint) => s3::Region::Custom { region: args.s3_region.clone(), endpoint: endpoint.clone() },
+                None => args.s3_region.parse().context("无效的 s3 region")?,
+            };
+            let credentials = s3::creds::Credentials::default().context("无法加载 S3 凭证")?;
+            let bucket = s3::Bucket::new(name, region, credentials).context("无法初始化 S3 bucket")?;
+            // MinIO 等兼容服务需要 path-style 寻址
+            let bucket = if args.s3_endpoint.is_some() { bucket.with_path_style() } else { bucket };
+            Ok(Box::new(S3Backend { bucket: *bucket }))
+        }
+    }
+}
+
+fn run_upload(args: Arc<UploadArgs>) -> Result<()> {
 
     // 1. 初始化纯 Rust 数据库 redb
     let db = Arc::new(
@@ -126,11 +655,28 @@ fn main() -> Result<()> {
     let (tx, rx) = unbounded::<MavenArtifact>();
     let processed_poms = Arc::new(DashMap::new());
 
+    // 共享状态：计数器 + 关停标志, 供控制面与守护循环使用
+    let stats = Arc::new(Stats::default());
+    let shutdown = Arc::new(AtomicBool::new(false));
+
+    // 可选的控制面 HTTP 服务
+    if let Some(listen) = args.listen.clone() {
+        let stats_http = Arc::clone(&stats);
+        let shutdown_http = Arc::clone(&shutdown);
+        thread::spawn(move || {
+            if let Err(e) = serve_control(listen, stats_http, shutdown_http) {
+                eprintln!("控制面退出: {}", e);
+            }
+        });
+    }
+
     // 4. 扫描线程 (生产者)
     let args_scan = Arc::clone(&args);
     let pb_scan = upload_pb.clone();
     let processed_ref = Arc::clone(&processed_poms);
     let root_ref = root_path.clone();
+    let stats_scan = Arc::clone(&stats);
+    let shutdown_scan = Arc::clone(&shutdown);
 
     thread::spawn(move || {
         WalkDir::new(&args_scan.dir)
@@ -140,22 +686,36 @@ fn main() -> Result<()> {
             .for_each(|entry| {
                 let path = entry.path();
                 let name = path.file_name().unwrap_or_default().to_string_lossy();
-                
+
                 if name.ends_with(".pom") || name == "pom.xml" {
                     if let Ok(art) = extract_full_artifact(&path, &root_ref) {
                         if is_excluded(&art, &args_scan, &pb_scan) { return; }
                         if processed_ref.insert(path.to_path_buf(), ()).is_none() {
                             pb_scan.inc_length(1);
+                            stats_scan.queued.fetch_add(1, Ordering::Relaxed);
                             let _ = tx.send(art);
                         }
                     }
                 }
             });
+
+        // 守护模式：扫描结束后保持 sender 存活, 改由文件监听驱动
+        if args_scan.watch {
+            let controller = Controller { tx, stats: stats_scan, shutdown: shutdown_scan };
+            let _ = watch_directory(controller, &args_scan, &root_ref);
+        }
     });
 
     // 5. 上传逻辑 (消费者)
-    let client = create_pure_rust_client()?;
-    let client = Arc::new(client);
+    // 全局限速器 (未设置 --rate 时为 None, 不做限速)
+    let limiter = args.rate.map(|rate| {
+        let burst = args.burst.unwrap_or(rate);
+        Arc::new(RateLimiter::new(rate, burst))
+    });
+    // 按 --backend 选择存储后端, 统一通过 Backend trait 落盘/推送
+    let backend = build_backend(&args, limiter)?;
+    // 重试耗尽仍失败的构件: URL -> 最后一次错误, 结束后写成 failed.json
+    let failures: DashMap<String, String> = DashMap::new();
     // 显式使用 Rayon 的桥接
     let parallel_iter = ParallelBridge::par_bridge(rx.into_iter());
     ParallelIterator::for_each(parallel_iter, |artifact| {
@@ -163,15 +723,401 @@ fn main() -> Result<()> {
         let raw_url = if is_snapshot { args.snapshot_url.as_ref().unwrap_or(&args.url) } else { &args.url };
         let base_url = if raw_url.ends_with('/') { raw_url.to_string() } else { format!("{}/", raw_url) };
 
-        upload_pb.set_message(format!("{}:{}", artifact.artifact_id, artifact.version));
-        
+        let label = format!("{}:{}", artifact.artifact_id, artifact.version);
+        upload_pb.set_message(label.clone());
+        *stats.current.lock().unwrap() = label;
+
         for (f_path, remote_ext) in &artifact.files {
-            let _ = upload_file(&client, &base_url, &args, &artifact, f_path, remote_ext, &upload_pb, &db);
+            let _ = upload_file(backend.as_ref(), &base_url, &args, &artifact, f_path, remote_ext, &upload_pb, &db, &stats, &failures);
         }
         upload_pb.inc(1);
     });
 
+    if args.dry_run {
+        upload_pb.finish_and_clear();
+        println!(
+            "🔎 预演: 将上传 {} 个文件, 共 {} 字节 ({:.2} MiB); 跳过 {} 个",
+            stats.uploaded.load(Ordering::Relaxed),
+            stats.bytes.load(Ordering::Relaxed),
+            stats.bytes.load(Ordering::Relaxed) as f64 / 1024.0 / 1024.0,
+            stats.skipped.load(Ordering::Relaxed),
+        );
+        return Ok(());
+    }
+
     upload_pb.finish_with_message("✅ 任务完成");
+    write_failure_report(&failures, &upload_pb)?;
+    Ok(())
+}
+
+/// 对已排序样本取百分位: 取下标 round(p/100 * (n-1)) 处的样本, 不做插值
+fn percentile(sorted: &[f64], p: f64) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let idx = ((p / 100.0) * (sorted.len() - 1) as f64).round() as usize;
+    sorted[idx]
+}
+
+fn run_benchmark(args: BenchmarkArgs) -> Result<()> {
+    let client = Arc::new(create_pure_rust_client()?);
+    let base_url = if args.url.ends_with('/') { args.url.clone() } else { format!("{}/", args.url) };
+    let with_auth = |req: reqwest::blocking::RequestBuilder| match &args.username {
+        Some(u) => req.basic_auth(u, args.password.clone()),
+        None => req,
+    };
+
+    // 1. TLS 握手 + 往返延迟: 多次 HEAD 取百分位
+    println!("⏱  测量延迟 (HEAD x 10) ...");
+    let mut latencies: Vec<f64> = Vec::new();
+    for _ in 0..10 {
+        let start = Instant::now();
+        let _ = with_auth(client.head(&base_url)).send();
+        latencies.push(start.elapsed().as_secs_f64() * 1000.0);
+    }
+    latencies.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    println!(
+        "   延迟 p50={:.1}ms p95={:.1}ms",
+        percentile(&latencies, 50.0),
+        percentile(&latencies, 95.0)
+    );
+
+    // 2. 递增负载吞吐: 64 KiB -> 64 MiB
+    println!("📤 测量吞吐 (合成负载) ...");
+    let mut size = 64 * 1024usize;
+    while size <= 64 * 1024 * 1024 {
+        let payload = vec![0u8; size];
+        let url = format!("{}{}/bench-{}", base_url, args.temp_path, size);
+        let start = Instant::now();
+        let resp = with_auth(client.put(&url)).body(payload).send();
+        let elapsed = start.elapsed().as_secs_f64();
+        match resp {
+            Ok(r) if r.status().is_success() || r.status().is_redirection() => {
+                let mbps = size as f64 / 1024.0 / 1024.0 / elapsed;
+                println!("   {:>6} KiB  {:>7.2} MiB/s", size / 1024, mbps);
+            }
+            Ok(r) => println!("   {:>6} KiB  服务端拒绝 ({})", size / 1024, r.status()),
+            Err(e) => println!("   {:>6} KiB  失败: {}", size / 1024, e),
+        }
+        size *= 4;
+    }
+
+    // 3. 有效并行度: N 个 worker 同时 PUT 同一尺寸负载
+    println!("🧵 测量并行度 (parallelism={}) ...", args.parallelism);
+    let pool = rayon::ThreadPoolBuilder::new().num_threads(args.parallelism).build()?;
+    let payload_size = 4 * 1024 * 1024usize;
+    let per_durations: Mutex<Vec<f64>> = Mutex::new(Vec::new());
+    let wall_start = Instant::now();
+    pool.install(|| {
+        use rayon::prelude::*;
+        (0..args.parallelism).into_par_iter().for_each(|i| {
+            let payload = vec![0u8; payload_size];
+            let url = format!("{}{}/bench-par-{}", base_url, args.temp_path, i);
+            let start = Instant::now();
+            let _ = with_auth(client.put(&url)).body(payload).send();
+            per_durations.lock().unwrap().push(start.elapsed().as_secs_f64());
+        });
+    });
+    let wall = wall_start.elapsed().as_secs_f64();
+    let sum: f64 = per_durations.lock().unwrap().iter().sum();
+    let effective = if wall > 0.0 { sum / wall } else { 0.0 };
+    println!("   有效并行度 ≈ {:.2}x (墙钟 {:.2}s, 累计 {:.2}s)", effective, wall, sum);
+
+    // 4. 清理临时对象: 兑现 "不改动真实构件" 的承诺
+    println!("🧹 清理临时对象 ...");
+    let mut temp_urls = Vec::new();
+    let mut size = 64 * 1024usize;
+    while size <= 64 * 1024 * 1024 {
+        temp_urls.push(format!("{}{}/bench-{}", base_url, args.temp_path, size));
+        size *= 4;
+    }
+    for i in 0..args.parallelism {
+        temp_urls.push(format!("{}{}/bench-par-{}", base_url, args.temp_path, i));
+    }
+    for url in &temp_urls {
+        let _ = with_auth(client.delete(url)).send();
+    }
+
+    Ok(())
+}
+
+/// 将失败清单写成 failed.json, 便于仅针对失败项重跑
+fn write_failure_report(failures: &DashMap<String, String>, pb: &ProgressBar) -> Result<()> {
+    if failures.is_empty() {
+        return Ok(());
+    }
+    let map: serde_json::Map<String, serde_json::Value> = failures
+        .iter()
+        .map(|e| (e.key().clone(), serde_json::Value::String(e.value().clone())))
+        .collect();
+    let json = serde_json::to_string_pretty(&serde_json::Value::Object(map))?;
+    fs::write("failed.json", json)?;
+    pb.println(format!("⚠️  {} 个构件失败, 详见 failed.json", failures.len()));
+    Ok(())
+}
+
+fn run_download(args: Arc<DownloadArgs>) -> Result<()> {
+    // 1. 复用同一套 redb 状态库，Key 为源文件 URL，Value 为远程 Last-Modified
+    let db = Arc::new(
+        Database::builder()
+            .create(&args.db_path)
+            .context("无法打开/创建 redb 数据库")?
+    );
+    {
+        let write_txn = db.begin_write()?;
+        { let _ = write_txn.open_table(TABLE)?; }
+        write_txn.commit()?;
+    }
+
+    let pb = ProgressBar::new(0);
+    pb.set_style(ProgressStyle::default_bar()
+        .template("{spinner:.blue} [{bar:40.cyan/blue}] {pos}/{len} {msg} ({percent}%)")?
+        .progress_chars("#>-"));
+
+    let base_url = if args.url.ends_with('/') { args.url.clone() } else { format!("{}/", args.url) };
+    let client = Arc::new(create_pure_rust_client()?);
+    let (tx, rx) = unbounded::<String>();
+
+    // 2. 爬取线程 (生产者)：按组前缀遍历远程目录索引
+    let producer_client = Arc::clone(&client);
+    let args_scan = Arc::clone(&args);
+    let pb_scan = pb.clone();
+    let base_scan = base_url.clone();
+    thread::spawn(move || {
+        for group in &args_scan.groups {
+            let group_path = group.replace('.', "/");
+            let start = format!("{}{}/", base_scan, group_path.trim_end_matches('/'));
+            crawl_index(&producer_client, &start, &args_scan, &tx, &pb_scan);
+        }
+    });
+
+    // 3. 下载逻辑 (消费者)：与 upload 共用 Rayon 桥接
+    // 校验失败/下载失败的文件: URL -> 原因, 结束后写 failed.json 并以非零返回
+    let failures: DashMap<String, String> = DashMap::new();
+    let parallel_iter = ParallelBridge::par_bridge(rx.into_iter());
+    ParallelIterator::for_each(parallel_iter, |file_url| {
+        if let Err(e) = download_file(&client, &base_url, &file_url, &args, &pb, &db, &failures) {
+            failures.insert(file_url.clone(), e.to_string());
+        }
+        pb.inc(1);
+    });
+
+    pb.finish_with_message("✅ 镜像完成");
+    write_failure_report(&failures, &pb)?;
+    if !failures.is_empty() {
+        return Err(anyhow::anyhow!("{} 个文件下载/校验失败, 详见 failed.json", failures.len()));
+    }
+    Ok(())
+}
+
+/// 为请求附加 basic auth (用户名/密码可缺省)
+fn with_download_auth(
+    req: reqwest::blocking::RequestBuilder,
+    args: &DownloadArgs,
+) -> reqwest::blocking::RequestBuilder {
+    match &args.username {
+        Some(u) => req.basic_auth(u, args.password.clone()),
+        None => req,
+    }
+}
+
+/// 从目录索引 HTML 中提取相对链接 (跳过排序参数、父目录、绝对链接)
+fn parse_links(html: &str) -> Vec<String> {
+    let mut out = Vec::new();
+    for part in html.split("href=\"").skip(1) {
+        if let Some(end) = part.find('"') {
+            let link = &part[..end];
+            if link.is_empty()
+                || link.starts_with('?')
+                || link.starts_with('#')
+                || link.starts_with('/')
+                || link.starts_with("..")
+                || link.contains("://")
+            {
+                continue;
+            }
+            out.push(link.to_string());
+        }
+    }
+    out
+}
+
+/// 递归爬取远程目录树，把真实构件 URL 投递到通道
+fn crawl_index(
+    client: &reqwest::blocking::Client,
+    url: &str,
+    args: &DownloadArgs,
+    tx: &Sender<String>,
+    pb: &ProgressBar,
+) {
+    let body = match with_download_auth(client.get(url), args)
+        .send()
+        .and_then(|r| r.error_for_status())
+        .and_then(|r| r.text())
+    {
+        Ok(b) => b,
+        Err(_) => return,
+    };
+    for link in parse_links(&body) {
+        let child = format!("{}{}", url, link);
+        if link.ends_with('/') {
+            crawl_index(client, &child, args, tx, pb);
+        } else if !is_checksum_ext(&link) && !link.starts_with("maven-metadata") {
+            pb.inc_length(1);
+            let _ = tx.send(child);
+        }
+    }
+}
+
+/// 解析 HTTP `Last-Modified` 头为 Unix 秒, 无法解析时回退 0
+fn parse_last_modified(headers: &reqwest::header::HeaderMap) -> u64 {
+    headers
+        .get(reqwest::header::LAST_MODIFIED)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|s| httpdate::parse_http_date(s).ok())
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// 下载单个远程构件：校验服务端 .sha1 后写入本地 Maven 布局
+fn download_file(
+    client: &reqwest::blocking::Client,
+    base_url: &str,
+    file_url: &str,
+    args: &DownloadArgs,
+    pb: &ProgressBar,
+    db: &Database,
+    failures: &DashMap<String, String>,
+) -> Result<()> {
+    let relative = file_url.strip_prefix(base_url).unwrap_or(file_url);
+    let local_path = Path::new(&args.dir).join(relative);
+    let file_name = local_path.file_name().unwrap_or_default().to_string_lossy().to_string();
+
+    // 先 HEAD 取 Last-Modified 判断是否可跳过 (断点续传).
+    // 服务端不带 Last-Modified 时 last_mod 为 0, 但只要本地文件已存在且上次也记的是 0,
+    // 仍会命中跳过——否则每次都会重下, 违背"中断可续"的目标.
+    let head = with_download_auth(client.head(file_url), args).send();
+    let last_mod = head.as_ref().map(|r| parse_last_modified(r.headers())).unwrap_or(0);
+    if !args.force && local_path.exists() {
+        let read_txn = db.begin_read()?;
+        let table = read_txn.open_table(TABLE)?;
+        if table.get(file_url)?.map_or(false, |v| v.value() == last_mod) {
+            pb.println(format!("  [-] 本地(DB)已存在: {}", file_name));
+            return Ok(());
+        }
+    }
+
+    let resp = with_download_auth(client.get(file_url), args).send()?.error_for_status()?;
+    let bytes = resp.bytes()?.to_vec();
+
+    // 校验服务端提供的 .sha1 (缺失则跳过校验)
+    let sha1_url = format!("{}.sha1", file_url);
+    if let Ok(r) = with_download_auth(client.get(&sha1_url), args).send() {
+        if r.status().is_success() {
+            if let Ok(text) = r.text() {
+                let expected = text.split_whitespace().next().unwrap_or("").to_lowercase();
+                if let Some(actual) = compute_checksum("sha1", &bytes) {
+                    if !expected.is_empty() && expected != actual {
+                        // 不静默丢弃: 记入失败清单, 让本次运行以非零结束
+                        pb.println(format!("  [❌] 校验和不匹配, 跳过: {}", file_name));
+                        failures.insert(
+                            file_url.to_string(),
+                            format!("sha1 不匹配: 期望 {}, 实得 {}", expected, actual),
+                        );
+                        return Ok(());
+                    }
+                }
+            }
+        }
+    }
+
+    if let Some(parent) = local_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&local_path, &bytes)?;
+    save_db_status(db, file_url, last_mod)?;
+    pb.println(format!("  [+] 下载成功: {}", file_name));
+    Ok(())
+}
+
+/// 判断一个远程后缀是否本身就是校验和文件 (如 `jar.sha1`)
+fn is_checksum_ext(ext: &str) -> bool {
+    ext.ends_with("md5") || ext.ends_with("sha1") || ext.ends_with("sha256") || ext.ends_with("sha512")
+}
+
+/// 将字节序列编码为小写十六进制字符串
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// 在内存中计算指定算法的摘要, 返回十六进制字符串
+fn compute_checksum(algo: &str, data: &[u8]) -> Option<String> {
+    use sha2::Digest;
+    match algo {
+        "md5" => { let mut h = md5::Md5::new(); h.update(data); Some(to_hex(&h.finalize())) }
+        "sha1" => { let mut h = sha1::Sha1::new(); h.update(data); Some(to_hex(&h.finalize())) }
+        "sha256" => { let mut h = sha2::Sha256::new(); h.update(data); Some(to_hex(&h.finalize())) }
+        "sha512" => { let mut h = sha2::Sha512::new(); h.update(data); Some(to_hex(&h.finalize())) }
+        _ => None,
+    }
+}
+
+/// 将一条十六进制摘要作为微小 Body 写到 `<artifact>.<algo>`, 并记入 redb
+fn put_checksum(
+    backend: &(dyn Backend + Send + Sync),
+    coord: &Coord,
+    algo: &str,
+    digest: String,
+    args: &UploadArgs,
+    mtime: u64,
+    pb: &ProgressBar,
+    db: &Database,
+) -> Result<()> {
+    let cs_coord = coord.with_suffix(&format!(".{}", algo));
+    if !args.force {
+        let read_txn = db.begin_read()?;
+        let table = read_txn.open_table(TABLE)?;
+        if table.get(cs_coord.url.as_str())?.map_or(false, |v| v.value() == mtime) {
+            return Ok(());
+        }
+    }
+    match backend.put(&cs_coord, digest.into_bytes()) {
+        Ok(()) => save_db_status(db, &cs_coord.url, mtime)?,
+        Err(e) => pb.println(format!("  [❌] 校验和失败: {} - {}", cs_coord.url, e)),
+    }
+    Ok(())
+}
+
+/// 构件本身被跳过 (redb/远程已存在) 时, 为其补传缺失的校验和伴随文件
+fn backfill_checksums(
+    backend: &(dyn Backend + Send + Sync),
+    coord: &Coord,
+    file_path: &Path,
+    remote_ext: &str,
+    args: &UploadArgs,
+    mtime: u64,
+    pb: &ProgressBar,
+    db: &Database,
+) -> Result<()> {
+    if is_checksum_ext(remote_ext) {
+        return Ok(());
+    }
+    let needed: Vec<&String> = args
+        .checksums
+        .iter()
+        .filter(|algo| args.regen_checksums || !PathBuf::from(format!("{}.{}", file_path.display(), algo)).exists())
+        .collect();
+    if needed.is_empty() {
+        return Ok(());
+    }
+    // 仅在确有缺口时才读取文件计算摘要
+    let data = fs::read(file_path)?;
+    for algo in needed {
+        if let Some(digest) = compute_checksum(algo, &data) {
+            let _ = put_checksum(backend, coord, algo, digest, args, mtime, pb, db);
+        }
+    }
     Ok(())
 }
 
@@ -205,7 +1151,7 @@ fn extract_full_artifact(pom_path: &Path, root_path: &Path) -> Result<MavenArtif
     Ok(MavenArtifact { group_id, artifact_id, version, files })
 }
 
-fn is_excluded(art: &MavenArtifact, args: &Args, pb: &ProgressBar) -> bool {
+fn is_excluded(art: &MavenArtifact, args: &UploadArgs, pb: &ProgressBar) -> bool {
     for pattern in &args.exclude {
         if art.artifact_id.contains(pattern) || art.group_id.contains(pattern) {
             pb.println(format!("  [🚫] 匹配排除规则 '{}': {}", pattern, art.artifact_id));
@@ -226,18 +1172,21 @@ fn is_excluded(art: &MavenArtifact, args: &Args, pb: &ProgressBar) -> bool {
 }
 
 fn upload_file(
-    client: &reqwest::blocking::Client,
+    backend: &(dyn Backend + Send + Sync),
     base_url: &str,
-    args: &Args,
+    args: &UploadArgs,
     artifact: &MavenArtifact,
     file_path: &Path,
     remote_ext: &str,
     pb: &ProgressBar,
     db: &Database,
+    stats: &Stats,
+    failures: &DashMap<String, String>,
 ) -> Result<()> {
     let group_path = artifact.group_id.replace('.', "/");
     let file_name = format!("{}-{}.{}", artifact.artifact_id, artifact.version, remote_ext);
-    let target_url = format!("{}{}/{}/{}/{}", base_url, group_path, artifact.artifact_id, artifact.version, file_name);
+    let relative = format!("{}/{}/{}/{}", group_path, artifact.artifact_id, artifact.version, file_name);
+    let coord = Coord { url: format!("{}{}", base_url, relative), relative };
 
     let mtime = fs::metadata(file_path)?.modified()?.duration_since(UNIX_EPOCH)?.as_secs();
 
@@ -246,40 +1195,72 @@ fn upload_file(
         let skip = {
             let read_txn = db.begin_read()?;
             let table = read_txn.open_table(TABLE)?;
-            table.get(target_url.as_str())?.map_or(false, |v| v.value() == mtime)
+            table.get(coord.url.as_str())?.map_or(false, |v| v.value() == mtime)
         };
         if skip {
+            stats.skipped.fetch_add(1, Ordering::Relaxed);
             pb.println(format!("  [-] 远程(DB)已存在: {}", file_name));
-            return Ok(()); 
+            // 构件已在, 但可能缺校验和伴随文件, 补传之
+            if !args.dry_run {
+                backfill_checksums(backend, &coord, file_path, remote_ext, args, mtime, pb, db)?;
+            }
+            return Ok(());
         }
 
-        let resp = client.head(&target_url).basic_auth(&args.username, Some(&args.password)).send();
-        if let Ok(r) = resp {
-            if r.status().is_success() {
-                save_db_status(db, &target_url, mtime)?;
-                pb.println(format!("  [-] 远程已存在: {}", file_name));
-                return Ok(());
-            }
+        // 预演模式下不向目标发起任何请求 (包括 HEAD)
+        if !args.dry_run && backend.exists(&coord)?.is_some() {
+            save_db_status(db, &coord.url, mtime)?;
+            stats.skipped.fetch_add(1, Ordering::Relaxed);
+            pb.println(format!("  [-] 远程已存在: {}", file_name));
+            backfill_checksums(backend, &coord, file_path, remote_ext, args, mtime, pb, db)?;
+            return Ok(());
         }
     }
 
+    // 预演模式: 只统计将上传的数量与字节数
+    if args.dry_run {
+        let len = fs::metadata(file_path)?.len();
+        stats.uploaded.fetch_add(1, Ordering::Relaxed);
+        stats.bytes.fetch_add(len, Ordering::Relaxed);
+        pb.println(format!("  [预演] 将上传: {} ({} bytes)", file_name, len));
+        return Ok(());
+    }
+
     let data = fs::read(file_path)?;
-    let put_resp = client.put(&target_url).basic_auth(&args.username, Some(&args.password)).body(data).send();
-
-    match put_resp {
-        Ok(resp) => {
-            let status = resp.status();
-            if status.is_success() {
-                if !remote_ext.contains("sha1") && !remote_ext.contains("md5") {
-                    pb.println(format!("  [+] 上传成功: {}", file_name));
-                }
-                save_db_status(db, &target_url, mtime)?;
-            } else {
-                let msg = resp.text().unwrap_or_default();
-                pb.println(format!("  [❌] 失败 ({}): {} - {}", status, file_name, msg));
+    let len = data.len() as u64;
+
+    // 边读边算：对真实构件 (非校验和文件) 预先计算缺失的校验和
+    let mut pending_checksums: Vec<(String, String)> = Vec::new();
+    if !is_checksum_ext(remote_ext) {
+        for algo in &args.checksums {
+            let companion = PathBuf::from(format!("{}.{}", file_path.display(), algo));
+            if companion.exists() && !args.regen_checksums {
+                continue;
+            }
+            if let Some(digest) = compute_checksum(algo, &data) {
+                pending_checksums.push((algo.clone(), digest));
             }
         }
-        Err(e) => pb.println(format!("  [!] 网络错误: {}", e)),
+    }
+
+    match backend.put(&coord, data) {
+        Ok(()) => {
+            if !remote_ext.contains("sha1") && !remote_ext.contains("md5") {
+                pb.println(format!("  [+] 上传成功: {}", file_name));
+            }
+            stats.uploaded.fetch_add(1, Ordering::Relaxed);
+            stats.bytes.fetch_add(len, Ordering::Relaxed);
+            save_db_status(db, &coord.url, mtime)?;
+            // 上传本地缺失的校验和伴随文件
+            for (algo, digest) in pending_checksums {
+                let _ = put_checksum(backend, &coord, &algo, digest, args, mtime, pb, db);
+            }
+        }
+        Err(e) => {
+            stats.failed.fetch_add(1, Ordering::Relaxed);
+            failures.insert(coord.url.clone(), e.to_string());
+            pb.println(format!("  [❌] 失败: {} - {}", file_name, e));
+        }
     }
     Ok(())
 }